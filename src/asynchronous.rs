@@ -0,0 +1,436 @@
+//! Async mirror of the `serializer`/`channel`/`server` abstractions, built on
+//! `tokio::io::AsyncRead`/`AsyncWrite` instead of the blocking `std::io`
+//! traits, so a daemon can drive thousands of connections without one OS
+//! thread each.
+//!
+//! The trait methods return a boxed, `Send` future (rather than relying on
+//! plain `async fn` in trait, whose returned opaque future isn't provably
+//! `Send` through a generic bound) so that `spawn`'s `tokio::spawn` actually
+//! accepts the resulting task.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::serializer::DEFAULT_MAX_FRAME_LEN;
+
+/// Async mirror of `crate::serializer::Serializer`.
+pub trait AsyncSerializer<R, W>: Send + Sync
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin + Send
+{
+    type Error: Send;
+    type Message: Send;
+
+    fn try_read<'a>(
+        &'a self,
+        reader: &'a mut R
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Self::Message>, Self::Error>> + Send + 'a>>;
+
+    fn read<'a>(
+        &'a self,
+        reader: &'a mut R
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Message, Self::Error>> + Send + 'a>> {
+        Box::pin(async move {
+            loop {
+                if let Some(message) = self.try_read(reader).await? {
+                    return Ok(message);
+                }
+            }
+        })
+    }
+
+    fn write<'a>(
+        &'a self,
+        writer: &'a mut W,
+        message: Self::Message
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send + 'a>>;
+}
+
+/// Implement this trait and wrap it in `AsyncJson` to get a length-delimited,
+/// JSON-encoded `AsyncSerializer` for free, analogous to
+/// `crate::serializer::JsonSerializer` (and using the same 4 bytes
+/// big-endian length prefix as `crate::serializer::LengthDelimited`, so the
+/// wire format matches the blocking side).
+#[cfg(feature = "json-serializer")]
+pub trait AsyncJsonSerializer {
+    type Error: From<std::io::Error> + From<serde_json::Error> + Send;
+    type Message: serde::Serialize + serde::de::DeserializeOwned + Send;
+}
+
+/// Adapts an `AsyncJsonSerializer` marker into a full `AsyncSerializer` impl.
+///
+/// This indirection (rather than a blanket `impl<S: AsyncJsonSerializer>
+/// AsyncSerializer for S`) is what lets `json-serializer` coexist with
+/// `messagepack-serializer`: two blanket impls of the same trait for a bare
+/// `S` conflict (`E0119`) regardless of whether a concrete `S` could ever
+/// implement both marker traits, since the compiler can't prove the impls
+/// are disjoint. Keying each codec off its own wrapper type sidesteps that.
+#[cfg(feature = "json-serializer")]
+pub struct AsyncJson<S>(pub S);
+
+#[cfg(feature = "json-serializer")]
+impl<R, W, S> AsyncSerializer<R, W> for AsyncJson<S>
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin + Send,
+    S: AsyncJsonSerializer + Send + Sync
+{
+    type Error = S::Error;
+    type Message = S::Message;
+
+    fn try_read<'a>(
+        &'a self,
+        reader: &'a mut R
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Self::Message>, Self::Error>> + Send + 'a>> {
+        Box::pin(async move {
+            let Some(payload) = read_frame(reader).await? else {
+                return Ok(None);
+            };
+
+            let message = serde_json::from_slice(&payload)?;
+
+            Ok(Some(message))
+        })
+    }
+
+    fn write<'a>(
+        &'a self,
+        writer: &'a mut W,
+        message: Self::Message
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send + 'a>> {
+        Box::pin(async move {
+            let payload = serde_json::to_vec(&message)?;
+
+            write_frame(writer, &payload).await?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Implement this trait and wrap it in `AsyncMessagePack` to get a
+/// length-delimited, MessagePack-encoded `AsyncSerializer` for free,
+/// analogous to `crate::serializer::MessagePackSerializer`.
+#[cfg(feature = "messagepack-serializer")]
+pub trait AsyncMessagePackSerializer {
+    type Error: From<std::io::Error> + From<rmp_serde::encode::Error> + From<rmp_serde::decode::Error> + Send;
+    type Message: serde::Serialize + serde::de::DeserializeOwned + Send;
+}
+
+/// See `AsyncJson` — same wrapper-type trick to avoid conflicting blanket
+/// impls when both `json-serializer` and `messagepack-serializer` are
+/// enabled.
+#[cfg(feature = "messagepack-serializer")]
+pub struct AsyncMessagePack<S>(pub S);
+
+#[cfg(feature = "messagepack-serializer")]
+impl<R, W, S> AsyncSerializer<R, W> for AsyncMessagePack<S>
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin + Send,
+    S: AsyncMessagePackSerializer + Send + Sync
+{
+    type Error = S::Error;
+    type Message = S::Message;
+
+    fn try_read<'a>(
+        &'a self,
+        reader: &'a mut R
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Self::Message>, Self::Error>> + Send + 'a>> {
+        Box::pin(async move {
+            let Some(payload) = read_frame(reader).await? else {
+                return Ok(None);
+            };
+
+            let message = rmp_serde::from_slice(&payload)?;
+
+            Ok(Some(message))
+        })
+    }
+
+    fn write<'a>(
+        &'a self,
+        writer: &'a mut W,
+        message: Self::Message
+    ) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send + 'a>> {
+        Box::pin(async move {
+            let payload = rmp_serde::to_vec(&message)?;
+
+            write_frame(writer, &payload).await?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Read one 4 bytes big-endian length-prefixed frame, returning `Ok(None)`
+/// cleanly on EOF before any byte of the prefix is read and a hard
+/// `UnexpectedEof` if the peer dies mid-prefix or mid-payload.
+///
+/// Rejects a declared length over `DEFAULT_MAX_FRAME_LEN` before allocating
+/// the payload buffer, same as `crate::serializer::LengthDelimited`'s
+/// `max_frame_len` check — without it a peer can force an unbounded
+/// allocation per message with a single length field, which matters more
+/// here than on the blocking side since this module exists to hold many
+/// concurrent connections open at once.
+async fn read_frame<R: AsyncRead + Unpin + Send>(reader: &mut R) -> std::io::Result<Option<Vec<u8>>> {
+    let mut len_buf = [0u8; 4];
+    let mut read = 0;
+
+    while read < len_buf.len() {
+        match reader.read(&mut len_buf[read..]).await? {
+            0 if read == 0 => return Ok(None),
+            0 => return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof)),
+            n => read += n
+        }
+    }
+
+    let len = u32::from_be_bytes(len_buf);
+
+    if len > DEFAULT_MAX_FRAME_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("frame length {len} exceeds maximum allowed {DEFAULT_MAX_FRAME_LEN}")
+        ));
+    }
+
+    let mut payload = vec![0u8; len as usize];
+
+    reader.read_exact(&mut payload).await?;
+
+    Ok(Some(payload))
+}
+
+async fn write_frame<W: AsyncWrite + Unpin + Send>(writer: &mut W, payload: &[u8]) -> std::io::Result<()> {
+    let len = u32::try_from(payload.len()).map_err(|_| std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "message payload is too large to be framed with a u32 length prefix"
+    ))?;
+
+    writer.write_all(&len.to_be_bytes()).await?;
+    writer.write_all(payload).await?;
+    writer.flush().await?;
+
+    Ok(())
+}
+
+/// Async mirror of `crate::channel::OwnedChannel`.
+pub trait AsyncOwnedChannel<R, W, S>: Send
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin + Send,
+    S: AsyncSerializer<R, W>
+{
+    fn reader(&mut self) -> &mut R;
+    fn writer(&mut self) -> &mut W;
+    fn serializer(&self) -> &S;
+
+    fn try_read<'a>(
+        &'a mut self
+    ) -> Pin<Box<dyn Future<Output = Result<Option<S::Message>, S::Error>> + Send + 'a>>;
+
+    fn read<'a>(
+        &'a mut self
+    ) -> Pin<Box<dyn Future<Output = Result<S::Message, S::Error>> + Send + 'a>>;
+
+    fn write<'a>(
+        &'a mut self,
+        message: S::Message
+    ) -> Pin<Box<dyn Future<Output = Result<(), S::Error>> + Send + 'a>>;
+}
+
+/// Async mirror of `crate::channel::UniChannel`: owned read-write channel
+/// over two separate async reader/writer halves.
+pub struct AsyncUniChannel<R, W, S> {
+    reader: R,
+    writer: W,
+    serializer: S
+}
+
+impl<R, W, S> AsyncUniChannel<R, W, S> {
+    #[inline]
+    pub const fn new(reader: R, writer: W, serializer: S) -> Self {
+        Self { reader, writer, serializer }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> (R, W, S) {
+        (self.reader, self.writer, self.serializer)
+    }
+}
+
+impl<R, W, S> AsyncOwnedChannel<R, W, S> for AsyncUniChannel<R, W, S>
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin + Send,
+    S: AsyncSerializer<R, W>
+{
+    #[inline]
+    fn reader(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    #[inline]
+    fn writer(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    #[inline]
+    fn serializer(&self) -> &S {
+        &self.serializer
+    }
+
+    fn try_read<'a>(
+        &'a mut self
+    ) -> Pin<Box<dyn Future<Output = Result<Option<S::Message>, S::Error>> + Send + 'a>> {
+        Box::pin(self.serializer.try_read(&mut self.reader))
+    }
+
+    fn read<'a>(
+        &'a mut self
+    ) -> Pin<Box<dyn Future<Output = Result<S::Message, S::Error>> + Send + 'a>> {
+        Box::pin(self.serializer.read(&mut self.reader))
+    }
+
+    fn write<'a>(
+        &'a mut self,
+        message: S::Message
+    ) -> Pin<Box<dyn Future<Output = Result<(), S::Error>> + Send + 'a>> {
+        Box::pin(self.serializer.write(&mut self.writer, message))
+    }
+}
+
+/// Async mirror of `crate::channel::BiChannel`: owned read-write channel
+/// over a single async reader+writer stream (e.g. `tokio::net::TcpStream`).
+pub struct AsyncBiChannel<T, S> {
+    io: T,
+    serializer: S
+}
+
+impl<T, S> AsyncBiChannel<T, S> {
+    #[inline]
+    pub const fn new(io: T, serializer: S) -> Self {
+        Self { io, serializer }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> (T, S) {
+        (self.io, self.serializer)
+    }
+}
+
+impl<T, S> AsyncOwnedChannel<T, T, S> for AsyncBiChannel<T, S>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send,
+    S: AsyncSerializer<T, T>
+{
+    #[inline]
+    fn reader(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    #[inline]
+    fn writer(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    #[inline]
+    fn serializer(&self) -> &S {
+        &self.serializer
+    }
+
+    fn try_read<'a>(
+        &'a mut self
+    ) -> Pin<Box<dyn Future<Output = Result<Option<S::Message>, S::Error>> + Send + 'a>> {
+        Box::pin(self.serializer.try_read(&mut self.io))
+    }
+
+    fn read<'a>(
+        &'a mut self
+    ) -> Pin<Box<dyn Future<Output = Result<S::Message, S::Error>> + Send + 'a>> {
+        Box::pin(self.serializer.read(&mut self.io))
+    }
+
+    fn write<'a>(
+        &'a mut self,
+        message: S::Message
+    ) -> Pin<Box<dyn Future<Output = Result<(), S::Error>> + Send + 'a>> {
+        Box::pin(self.serializer.write(&mut self.io, message))
+    }
+}
+
+/// Spawn an async task which will listen to incoming messages in a loop,
+/// process them using the `messages_handler` and use `errors_handler` on any
+/// occuring errors, same as `crate::server::daemon` but driven by `tokio::spawn`
+/// instead of `std::thread::spawn`.
+///
+/// If `errors_handler` returns `true`, then the task will stop.
+pub fn spawn<R, W, S, C, H, E>(
+    mut channel: C,
+    messages_handler: H,
+    errors_handler: E
+) -> AsyncDaemon
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+    S: AsyncSerializer<R, W> + Send + Sync + 'static,
+    C: AsyncOwnedChannel<R, W, S> + Send + 'static,
+    H: Fn(S::Message) -> Option<S::Message> + Send + 'static,
+    E: Fn(S::Error) -> bool + Send + 'static
+{
+    let alive = Arc::new(AtomicBool::new(true));
+
+    {
+        let alive = alive.clone();
+
+        tokio::spawn(async move {
+            while alive.load(Ordering::Relaxed) {
+                let message = match channel.read().await {
+                    Ok(message) => message,
+
+                    Err(err) => {
+                        if (errors_handler)(err) {
+                            alive.store(false, Ordering::Release);
+                            break;
+                        }
+
+                        continue;
+                    }
+                };
+
+                if let Some(response) = (messages_handler)(message) {
+                    if let Err(err) = channel.write(response).await {
+                        if (errors_handler)(err) {
+                            alive.store(false, Ordering::Release);
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    AsyncDaemon(alive)
+}
+
+#[derive(Debug, Clone)]
+pub struct AsyncDaemon(Arc<AtomicBool>);
+
+impl AsyncDaemon {
+    /// Check if the underlying server task is still running.
+    #[inline]
+    pub fn is_alive(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Stop the underlying server task.
+    #[inline]
+    pub fn kill(self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
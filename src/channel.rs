@@ -3,6 +3,8 @@ use std::io::{Read, Write, Stdin, Stdout, Stderr};
 #[cfg(unix)]
 use std::os::unix::net::UnixStream;
 
+use std::net::{TcpStream, ToSocketAddrs};
+
 use crate::serializer::Serializer;
 
 /// A single-direction channel which can read messages using provided serializer.
@@ -46,6 +48,43 @@ pub trait OwnedChannel<R: Read, W: Write, S: Serializer<R, W>> {
     fn write(&mut self, message: S::Message) -> Result<(), S::Error>;
 }
 
+/// Streams which support bounding how long a blocking read can wait for
+/// data, implemented for the socket-backed streams used by `BiChannel`/
+/// `UniChannel`.
+pub trait ReadTimeout {
+    fn set_read_timeout(&self, timeout: Option<std::time::Duration>) -> std::io::Result<()>;
+}
+
+#[cfg(unix)]
+impl ReadTimeout for UnixStream {
+    #[inline]
+    fn set_read_timeout(&self, timeout: Option<std::time::Duration>) -> std::io::Result<()> {
+        UnixStream::set_read_timeout(self, timeout)
+    }
+}
+
+impl ReadTimeout for TcpStream {
+    #[inline]
+    fn set_read_timeout(&self, timeout: Option<std::time::Duration>) -> std::io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+/// Helper for `errors_handler` callbacks to recognize a read timing out (as
+/// opposed to the peer actually disconnecting), so a stalled connection can
+/// be retried instead of torn down. Implemented for `std::io::Error`, which
+/// is what `set_read_timeout` causes a blocked read to fail with.
+pub trait TimeoutError {
+    fn is_timeout(&self) -> bool;
+}
+
+impl TimeoutError for std::io::Error {
+    #[inline]
+    fn is_timeout(&self) -> bool {
+        matches!(self.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut)
+    }
+}
+
 /// Get read-only channel from the stdin stream.
 #[inline]
 pub fn stdin() -> ReadChannel<Stdin> {
@@ -100,6 +139,21 @@ where
     Ok(BiChannel::new(socket, serializer))
 }
 
+/// Get owned read-write TCP socket channel from the provided address and
+/// serializer.
+#[inline]
+pub fn tcp_socket<S>(
+    addr: impl ToSocketAddrs,
+    serializer: S
+) -> std::io::Result<BiChannel<TcpStream, S>>
+where
+    S: Serializer<TcpStream, TcpStream>
+{
+    let socket = TcpStream::connect(addr)?;
+
+    Ok(BiChannel::new(socket, serializer))
+}
+
 /// Read-only channel abstraction over the generic reader.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ReadChannel<R>(R);
@@ -234,6 +288,17 @@ impl<R, W, S> UniChannel<R, W, S> {
     }
 }
 
+impl<R: Read + ReadTimeout, W, S> UniChannel<R, W, S> {
+    /// Bound how long `try_read`/`read` may block waiting for the peer
+    /// before returning a timeout error, by setting a read timeout on the
+    /// underlying reader. Pass `None` to clear a previously set timeout.
+    pub fn with_read_timeout(self, timeout: Option<std::time::Duration>) -> std::io::Result<Self> {
+        self.reader.as_ref().set_read_timeout(timeout)?;
+
+        Ok(self)
+    }
+}
+
 impl<R, W, S> OwnedChannel<R, W, S> for UniChannel<R, W, S>
 where
     R: Read,
@@ -293,6 +358,17 @@ impl<T, S> BiChannel<T, S> {
     }
 }
 
+impl<T: ReadTimeout, S> BiChannel<T, S> {
+    /// Bound how long `try_read`/`read` may block waiting for the peer
+    /// before returning a timeout error, by setting a read timeout on the
+    /// underlying stream. Pass `None` to clear a previously set timeout.
+    pub fn with_read_timeout(self, timeout: Option<std::time::Duration>) -> std::io::Result<Self> {
+        self.io.set_read_timeout(timeout)?;
+
+        Ok(self)
+    }
+}
+
 impl<T, S> OwnedChannel<T, T, S> for BiChannel<T, S>
 where
     T: Read + Write,
@@ -0,0 +1,170 @@
+//! Passing file descriptors alongside messages over `UnixStream`-backed
+//! channels, using `SCM_RIGHTS` ancillary data.
+
+use std::io::{self, Cursor, Read};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixStream;
+
+use nix::sys::socket::{
+    ControlMessage,
+    ControlMessageOwned,
+    MsgFlags,
+    recvmsg,
+    sendmsg
+};
+
+use crate::serializer::{DEFAULT_MAX_FRAME_LEN, Serializer};
+
+/// Upper bound on the number of descriptors a single `recv_with_fds` call
+/// will accept, to keep the ancillary data buffer a fixed size.
+pub const MAX_ANCILLARY_FDS: usize = 16;
+
+/// A message paired with file descriptors that should travel alongside it as
+/// out-of-band `SCM_RIGHTS` ancillary data instead of being serialized into
+/// the payload bytes.
+#[derive(Debug)]
+pub struct WithFds<M, F> {
+    pub message: M,
+    pub fds: Vec<F>
+}
+
+impl<M> WithFds<M, RawFd> {
+    #[inline]
+    pub const fn new(message: M, fds: Vec<RawFd>) -> Self {
+        Self { message, fds }
+    }
+}
+
+/// Send `message` framed the same way `LengthDelimited` would, with `fds`
+/// attached to the same `sendmsg` call as `SCM_RIGHTS` ancillary data.
+/// Ownership of `fds` stays with the caller; they are not closed here.
+pub fn send_with_fds<S>(
+    stream: &UnixStream,
+    serializer: &S,
+    message: WithFds<S::Message, RawFd>
+) -> Result<(), S::Error>
+where
+    S: Serializer<Cursor<Vec<u8>>, Vec<u8>>,
+    S::Error: From<io::Error>
+{
+    let WithFds { message, fds } = message;
+
+    if fds.len() > MAX_ANCILLARY_FDS {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("cannot send {} fds in one message, MAX_ANCILLARY_FDS is {MAX_ANCILLARY_FDS}", fds.len())
+        ).into());
+    }
+
+    let mut payload = Vec::new();
+
+    serializer.write(&mut payload, message)?;
+
+    let len = u32::try_from(payload.len()).map_err(|_| io::Error::new(
+        io::ErrorKind::InvalidData,
+        "message payload is too large to be framed with a u32 length prefix"
+    ))?;
+
+    let mut framed = Vec::with_capacity(4 + payload.len());
+
+    framed.extend_from_slice(&len.to_be_bytes());
+    framed.extend_from_slice(&payload);
+
+    let iov = [io::IoSlice::new(&framed)];
+
+    let cmsgs = if fds.is_empty() {
+        Vec::new()
+    } else {
+        vec![ControlMessage::ScmRights(&fds)]
+    };
+
+    sendmsg::<()>(stream.as_raw_fd(), &iov, &cmsgs, MsgFlags::empty(), None)
+        .map_err(io::Error::from)?;
+
+    Ok(())
+}
+
+/// Receive a message together with any descriptors that were sent alongside
+/// it in the same `sendmsg` call, reconstructed as owned `OwnedFd`s.
+///
+/// Returns `Ok(None)` on a clean EOF before any byte of the next frame
+/// arrives; a partial length prefix is a hard error, same as
+/// `LengthDelimited::try_read`. A declared length over `DEFAULT_MAX_FRAME_LEN`
+/// is rejected before the payload buffer is allocated, same as
+/// `LengthDelimited::try_read`'s `max_frame_len` check.
+pub fn recv_with_fds<S>(
+    stream: &UnixStream,
+    serializer: &S
+) -> Result<Option<WithFds<S::Message, OwnedFd>>, S::Error>
+where
+    S: Serializer<Cursor<Vec<u8>>, Vec<u8>>,
+    S::Error: From<io::Error>
+{
+    let mut len_buf = [0u8; 4];
+    let mut read = 0;
+    let mut fds = Vec::new();
+
+    // Read the length prefix one `recvmsg` call at a time, same as
+    // `LengthDelimited::try_read`: a short read of 1-3 bytes is a normal
+    // occurrence on a stream socket, not EOF, so it must be looped rather
+    // than treated as a hard error. The descriptors always arrive attached
+    // to whichever call observes the first byte of the frame, so they're
+    // captured from every call until the prefix is complete.
+    while read < len_buf.len() {
+        let (n, call_fds) = recv_raw(stream, &mut len_buf[read..])?;
+
+        fds.extend(call_fds);
+
+        match n {
+            0 if read == 0 => return Ok(None),
+            0 => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            n => read += n
+        }
+    }
+
+    let len = u32::from_be_bytes(len_buf);
+
+    if len > DEFAULT_MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame length {len} exceeds maximum allowed {DEFAULT_MAX_FRAME_LEN}")
+        ).into());
+    }
+
+    let mut payload = vec![0u8; len as usize];
+
+    (&mut &*stream).read_exact(&mut payload)?;
+
+    let message = serializer.read(&mut Cursor::new(payload))?;
+
+    Ok(Some(WithFds { message, fds }))
+}
+
+fn recv_raw(stream: &UnixStream, buf: &mut [u8]) -> io::Result<(usize, Vec<OwnedFd>)> {
+    let mut iov = [io::IoSliceMut::new(buf)];
+    let mut cmsg_buf = nix::cmsg_space!([RawFd; MAX_ANCILLARY_FDS]);
+
+    let msg = recvmsg::<()>(stream.as_raw_fd(), &mut iov, Some(&mut cmsg_buf), MsgFlags::empty())
+        .map_err(io::Error::from)?;
+
+    // `cmsg_buf` only has room for `MAX_ANCILLARY_FDS` descriptors; if the
+    // peer attached more, the kernel truncates (and closes) the rest with no
+    // other signal but this flag. Silently continuing would hand the caller
+    // a `WithFds` that's missing descriptors it thinks it has.
+    if msg.flags.contains(MsgFlags::MSG_CTRUNC) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("peer sent more than MAX_ANCILLARY_FDS ({MAX_ANCILLARY_FDS}) fds, ancillary data was truncated")
+        ));
+    }
+
+    let mut fds = Vec::new();
+
+    for cmsg in msg.cmsgs() {
+        if let ControlMessageOwned::ScmRights(raw_fds) = cmsg {
+            fds.extend(raw_fds.into_iter().map(|fd| unsafe { OwnedFd::from_raw_fd(fd) }));
+        }
+    }
+
+    Ok((msg.bytes, fds))
+}
@@ -124,6 +124,17 @@ pub mod serializer;
 pub mod channel;
 pub mod server;
 pub mod client;
+pub mod listener;
+pub mod stream;
+
+#[cfg(feature = "async")]
+pub mod asynchronous;
+
+#[cfg(feature = "rpc")]
+pub mod rpc;
+
+#[cfg(all(unix, feature = "fd-passing"))]
+pub mod fd;
 
 pub mod prelude {
     pub use super::serializer::Serializer;
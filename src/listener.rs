@@ -0,0 +1,175 @@
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+
+use crate::channel::BiChannel;
+use crate::serializer::Serializer;
+use crate::server::{Daemon, daemon};
+
+/// Pool of `Daemon` handles spawned by a `Listener`'s accept loop, one per
+/// accepted connection.
+///
+/// `kill()` is cooperative, not forcible: it flips the flags the accept loop
+/// and every pooled `Daemon` check, but neither is interrupted out of a
+/// blocking call already in progress. The accept loop thread only notices
+/// once `accept()` returns (i.e. on the next incoming connection), and each
+/// connection's `Daemon` only notices once its current `channel.read()`
+/// returns (whenever the peer next sends something or disconnects; `listen`
+/// sets no read timeout on the streams it accepts). `is_alive()` reflects
+/// whether the accept loop itself is still running.
+#[derive(Clone)]
+pub struct ListenerHandle {
+    alive: Arc<AtomicBool>,
+    daemons: Arc<Mutex<Vec<Daemon>>>
+}
+
+impl ListenerHandle {
+    /// Check if the accept loop thread is still running.
+    #[inline]
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Relaxed)
+    }
+
+    /// Number of connections currently tracked as alive in the pool.
+    pub fn connections(&self) -> usize {
+        let mut daemons = self.daemons.lock().unwrap();
+
+        daemons.retain(Daemon::is_alive);
+        daemons.len()
+    }
+
+    /// Ask the accept loop and every pooled connection to stop. See the
+    /// struct-level docs: this is best-effort and doesn't interrupt a
+    /// thread that's already blocked in `accept()` or `channel.read()`.
+    pub fn kill(self) {
+        self.alive.store(false, Ordering::Release);
+
+        let daemons = std::mem::take(&mut *self.daemons.lock().unwrap());
+
+        for daemon in daemons {
+            daemon.kill();
+        }
+    }
+}
+
+/// Listener types `listen` can spawn an accept loop for: anything that
+/// yields a fresh, independently owned stream per accepted connection.
+/// Implemented for `UnixListener` and `TcpListener`, which is what lets
+/// `listen_unix`/`listen_tcp` share a single accept loop implementation.
+pub trait Accept {
+    type Stream: Send + 'static;
+
+    fn accept(&self) -> io::Result<Self::Stream>;
+}
+
+#[cfg(unix)]
+impl Accept for UnixListener {
+    type Stream = UnixStream;
+
+    #[inline]
+    fn accept(&self) -> io::Result<UnixStream> {
+        UnixListener::accept(self).map(|(stream, _addr)| stream)
+    }
+}
+
+impl Accept for TcpListener {
+    type Stream = TcpStream;
+
+    #[inline]
+    fn accept(&self) -> io::Result<TcpStream> {
+        TcpListener::accept(self).map(|(stream, _addr)| stream)
+    }
+}
+
+/// Spawn an accept loop for the given listener which, for every accepted
+/// connection, spawns a `Server` using a freshly built serializer and the
+/// shared `messages_handler` in its own background thread (via `daemon`).
+/// Dead connection threads are pruned from the pool as new connections
+/// arrive; `errors_handler` decides, per connection, whether an error is
+/// fatal to that connection only — it never affects the listener or any
+/// other connection.
+pub fn listen<L, S, H, E>(
+    listener: L,
+    build_serializer: impl Fn() -> S + Send + 'static,
+    messages_handler: H,
+    errors_handler: E
+) -> ListenerHandle
+where
+    L: Accept + Send + 'static,
+    S: Serializer<L::Stream, L::Stream> + Send + 'static,
+    L::Stream: std::io::Read + std::io::Write,
+    H: Fn(S::Message) -> Option<S::Message> + Send + Clone + 'static,
+    E: Fn(S::Error) -> bool + Send + Clone + 'static
+{
+    let alive = Arc::new(AtomicBool::new(true));
+    let daemons = Arc::new(Mutex::new(Vec::new()));
+
+    {
+        let alive = alive.clone();
+        let daemons = daemons.clone();
+
+        std::thread::spawn(move || {
+            while alive.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok(stream) => {
+                        let channel = BiChannel::new(stream, build_serializer());
+
+                        let connection = daemon(
+                            channel,
+                            messages_handler.clone(),
+                            errors_handler.clone()
+                        );
+
+                        let mut daemons = daemons.lock().unwrap();
+
+                        daemons.retain(Daemon::is_alive);
+                        daemons.push(connection);
+                    }
+
+                    Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+
+                    Err(_) => break
+                }
+            }
+
+            alive.store(false, Ordering::Release);
+        });
+    }
+
+    ListenerHandle { alive, daemons }
+}
+
+/// Like `listen`, specialized for a `UnixListener`.
+#[cfg(unix)]
+pub fn listen_unix<S, H, E>(
+    listener: UnixListener,
+    build_serializer: impl Fn() -> S + Send + 'static,
+    messages_handler: H,
+    errors_handler: E
+) -> ListenerHandle
+where
+    S: Serializer<UnixStream, UnixStream> + Send + 'static,
+    H: Fn(S::Message) -> Option<S::Message> + Send + Clone + 'static,
+    E: Fn(S::Error) -> bool + Send + Clone + 'static
+{
+    listen(listener, build_serializer, messages_handler, errors_handler)
+}
+
+/// Like `listen`, specialized for a `TcpListener`.
+pub fn listen_tcp<S, H, E>(
+    listener: TcpListener,
+    build_serializer: impl Fn() -> S + Send + 'static,
+    messages_handler: H,
+    errors_handler: E
+) -> ListenerHandle
+where
+    S: Serializer<TcpStream, TcpStream> + Send + 'static,
+    H: Fn(S::Message) -> Option<S::Message> + Send + Clone + 'static,
+    E: Fn(S::Error) -> bool + Send + Clone + 'static
+{
+    listen(listener, build_serializer, messages_handler, errors_handler)
+}
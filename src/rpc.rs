@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::channel::OwnedChannel;
+use crate::serializer::Serializer;
+
+/// A message type that carries its own expected answer type, so an
+/// `RpcClient` can return a strongly typed response for every request it
+/// sends instead of the caller having to downcast a generic reply.
+pub trait Request: Serialize {
+    type Response: Serialize + DeserializeOwned;
+}
+
+/// Wire envelope correlating a request with its eventual response. Both
+/// requests and responses travel as JSON-encoded `body` bytes, so the
+/// envelope's shape stays fixed regardless of the concrete `Request` type in
+/// use; a channel carrying `RawFrame` as its message type can be used with
+/// `RpcClient`/`dispatcher`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RawFrame {
+    pub id: u64,
+    pub body: Vec<u8>
+}
+
+/// Error returned by `RpcClient::call`: either the underlying channel failed,
+/// or the request/response body couldn't be encoded/decoded as JSON.
+#[derive(Debug)]
+pub enum RpcError<E> {
+    Channel(E),
+    Encode(serde_json::Error),
+    Decode(serde_json::Error)
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for RpcError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Channel(err) => write!(f, "channel error: {err}"),
+            Self::Encode(err) => write!(f, "failed to encode rpc request: {err}"),
+            Self::Decode(err) => write!(f, "failed to decode rpc response: {err}")
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for RpcError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Channel(err) => Some(err),
+            Self::Encode(err) | Self::Decode(err) => Some(err)
+        }
+    }
+}
+
+/// Client handle over an `OwnedChannel<R, W, S>` whose message type is
+/// `RawFrame`. Every `call` tags its request with a fresh monotonically
+/// increasing correlation id and blocks until a reply carrying the same id
+/// arrives, buffering any out-of-order replies for the waiter they actually
+/// belong to.
+pub struct RpcClient<R, W, S, C> {
+    channel: Mutex<C>,
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, RawFrame>>,
+    _io: std::marker::PhantomData<(R, W, S)>
+}
+
+impl<R, W, S, C> RpcClient<R, W, S, C>
+where
+    R: Read,
+    W: Write,
+    S: Serializer<R, W, Message = RawFrame>,
+    C: OwnedChannel<R, W, S>
+{
+    #[inline]
+    pub fn new(channel: C) -> Self {
+        Self {
+            channel: Mutex::new(channel),
+            next_id: AtomicU64::new(0),
+            pending: Mutex::new(HashMap::new()),
+            _io: std::marker::PhantomData
+        }
+    }
+
+    /// Send `request` and block until the matching response arrives,
+    /// regardless of how many other replies are interleaved with it on the
+    /// channel in the meantime.
+    pub fn call<Req: Request>(&self, request: Req) -> Result<Req::Response, RpcError<S::Error>> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        let body = serde_json::to_vec(&request).map_err(RpcError::Encode)?;
+
+        self.channel.lock().unwrap()
+            .write(RawFrame { id, body })
+            .map_err(RpcError::Channel)?;
+
+        loop {
+            // Hold the channel lock across the pending check, the read and the
+            // stash, so a reply stashed by another caller is always seen here
+            // before this caller blocks on the channel again.
+            let mut channel = self.channel.lock().unwrap();
+
+            if let Some(frame) = self.pending.lock().unwrap().remove(&id) {
+                return serde_json::from_slice(&frame.body).map_err(RpcError::Decode);
+            }
+
+            let frame = channel.read().map_err(RpcError::Channel)?;
+
+            if frame.id == id {
+                return serde_json::from_slice(&frame.body).map_err(RpcError::Decode);
+            }
+
+            self.pending.lock().unwrap().insert(frame.id, frame);
+        }
+    }
+}
+
+/// Build a `Server`-compatible handler out of a typed request handler: it
+/// decodes the inbound frame's JSON body into `Req`, runs `handler`, and
+/// re-attaches the original correlation id to the JSON-encoded response.
+///
+/// Frames that fail to decode as `Req` are silently dropped (no reply is
+/// sent for them) rather than killing the connection, since a malformed
+/// frame is a peer bug, not a channel error.
+pub fn dispatcher<Req, F>(handler: F) -> impl Fn(RawFrame) -> Option<RawFrame>
+where
+    Req: DeserializeOwned,
+    Req: Request,
+    F: Fn(Req) -> Req::Response
+{
+    move |frame: RawFrame| {
+        let request: Req = serde_json::from_slice(&frame.body).ok()?;
+
+        let response = handler(request);
+
+        let body = serde_json::to_vec(&response).ok()?;
+
+        Some(RawFrame { id: frame.id, body })
+    }
+}
@@ -2,9 +2,10 @@ use std::io::{Read, Write};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 use crate::serializer::Serializer;
-use crate::channel::OwnedChannel;
+use crate::channel::{OwnedChannel, TimeoutError};
 
 /// Spawn new thread with a new `Server` struct which will listen to incoming
 /// messages in a loop, process them using the `messages_handler` and use
@@ -47,6 +48,94 @@ where
     Daemon(alive)
 }
 
+/// Like `daemon`, but the connection's channel read is expected to be
+/// bounded with a timeout smaller than `interval` (e.g. via
+/// `BiChannel::with_read_timeout`). Whenever a read times out and `interval`
+/// has passed since the last inbound frame or the last heartbeat, `heartbeat`
+/// is written to the channel; if `max_missed` heartbeats in a row go
+/// unanswered, the thread stops and `on_disconnect` is called, instead of
+/// leaving the thread blocked on a stalled peer forever.
+///
+/// Any other error from `Server::update` is still handed to `errors_handler`,
+/// same as `daemon`. If writing a heartbeat fails, the thread stops without
+/// calling `errors_handler` or `on_disconnect`.
+#[allow(clippy::too_many_arguments)]
+pub fn daemon_with_heartbeat<R, W, S, C, H, E, D>(
+    channel: C,
+    messages_handler: H,
+    errors_handler: E,
+    heartbeat: S::Message,
+    interval: Duration,
+    max_missed: u32,
+    on_disconnect: D
+) -> Daemon
+where
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+    S: Serializer<R, W> + Send + 'static,
+    S::Message: Clone + Send,
+    S::Error: TimeoutError,
+    C: OwnedChannel<R, W, S> + Send + 'static,
+    H: Fn(S::Message) -> Option<S::Message> + Send + 'static,
+    E: Fn(S::Error) -> bool + Send + 'static,
+    D: FnOnce() + Send + 'static
+{
+    let mut server = Server::new(channel, messages_handler);
+
+    let alive = Arc::new(AtomicBool::new(true));
+
+    {
+        let alive = alive.clone();
+
+        std::thread::spawn(move || {
+            let mut last_seen = Instant::now();
+            let mut missed = 0;
+
+            while alive.load(Ordering::Relaxed) {
+                match server.update() {
+                    Ok(()) => {
+                        last_seen = Instant::now();
+                        missed = 0;
+                    }
+
+                    Err(err) if err.is_timeout() => {
+                        if last_seen.elapsed() < interval {
+                            continue;
+                        }
+
+                        if server.channel().write(heartbeat.clone()).is_err() {
+                            alive.store(false, Ordering::Release);
+
+                            break;
+                        }
+
+                        last_seen = Instant::now();
+                        missed += 1;
+
+                        if missed > max_missed {
+                            alive.store(false, Ordering::Release);
+
+                            on_disconnect();
+
+                            break;
+                        }
+                    }
+
+                    Err(err) => {
+                        if (errors_handler)(err) {
+                            alive.store(false, Ordering::Release);
+
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    Daemon(alive)
+}
+
 pub struct Server<R, W, S, C, H> {
     _reader: PhantomData<R>,
     _writer: PhantomData<W>,
@@ -0,0 +1,232 @@
+use std::io::{self, Read, Write};
+
+use crate::channel::OwnedChannel;
+use crate::serializer::Serializer;
+
+/// Bounded `Read` handle into a message's streamed body attachment.
+///
+/// The body is chunked on the wire as a 4 bytes big-endian length prefix
+/// followed by that many bytes, repeated until a zero-length chunk
+/// terminates it. Borrowing the channel's reader for the lifetime of this
+/// handle is what enforces the "next message can't be read until the body
+/// is consumed" invariant: the borrow checker won't let the caller call
+/// `OwnedChannel::read` again until the `ChannelStream` is dropped.
+pub struct ChannelStream<'a, R: Read> {
+    reader: &'a mut R,
+    remaining: u64,
+    // Set the moment a `read_chunk_len` call (including the one in `new`)
+    // returns 0. Without this, `read` can't tell "the terminator was just
+    // consumed, there's nothing left" from "a real chunk just finished
+    // draining, go check what comes next" — both leave `remaining == 0`. The
+    // former must never touch the reader again; the latter must probe for
+    // the next chunk length. Collapsing them used to make `read` issue a
+    // bogus extra `read_chunk_len` for the all-too-common no-body message,
+    // consuming 4 bytes that belonged to the following frame.
+    done: bool
+}
+
+impl<'a, R: Read> ChannelStream<'a, R> {
+    fn new(reader: &'a mut R) -> io::Result<Self> {
+        let remaining = read_chunk_len(reader)?;
+        let done = remaining == 0;
+
+        Ok(Self { reader, remaining, done })
+    }
+
+    /// Read and discard the rest of the body without materializing it, so
+    /// the channel becomes safe to read the next message from even if the
+    /// handler doesn't care about this attachment.
+    pub fn skip_to_end(mut self) -> io::Result<()> {
+        io::copy(&mut self, &mut io::sink())?;
+
+        Ok(())
+    }
+}
+
+impl<'a, R: Read> Read for ChannelStream<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.done {
+            return Ok(0);
+        }
+
+        if self.remaining == 0 {
+            self.remaining = read_chunk_len(self.reader)?;
+
+            if self.remaining == 0 {
+                self.done = true;
+
+                return Ok(0);
+            }
+        }
+
+        let max = buf.len().min(self.remaining as usize);
+
+        let read = self.reader.read(&mut buf[..max])?;
+
+        self.remaining -= read as u64;
+
+        Ok(read)
+    }
+}
+
+impl<'a, R: Read> Drop for ChannelStream<'a, R> {
+    fn drop(&mut self) {
+        // The caller may drop the body without reading it at all (or only
+        // partially); always drain the rest so the next `read` on the
+        // channel doesn't observe leftover chunk bytes as a new message.
+        let _ = io::copy(self, &mut io::sink());
+    }
+}
+
+fn read_chunk_len<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 4];
+
+    reader.read_exact(&mut buf)?;
+
+    Ok(u32::from_be_bytes(buf) as u64)
+}
+
+/// `OwnedChannel` adapter that always exchanges a streamed body trailer
+/// (zero-length if the caller had nothing to stream) alongside every
+/// message, so the plain `OwnedChannel::read`/`write` trait methods stay
+/// correct on their own: `write` appends the trailer automatically, and
+/// `read` drains it automatically before returning the message. This is
+/// what makes it safe to mix `Server`/`daemon`/`RpcClient` (which only know
+/// about the plain trait methods) with a peer that may or may not attach a
+/// body — reading through a `StreamingChannel` can never desync the framing.
+///
+/// Use `read_with_stream`/`write_with_stream` instead of the trait methods
+/// when you actually want to produce or consume the body incrementally.
+pub struct StreamingChannel<C>(C);
+
+impl<C> StreamingChannel<C> {
+    #[inline]
+    pub const fn new(channel: C) -> Self {
+        Self(channel)
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> C {
+        self.0
+    }
+}
+
+impl<R, W, S, C> OwnedChannel<R, W, S> for StreamingChannel<C>
+where
+    R: Read,
+    W: Write,
+    S: Serializer<R, W>,
+    S::Error: From<io::Error>,
+    C: OwnedChannel<R, W, S>
+{
+    #[inline]
+    fn reader(&mut self) -> &mut R {
+        self.0.reader()
+    }
+
+    #[inline]
+    fn writer(&mut self) -> &mut W {
+        self.0.writer()
+    }
+
+    #[inline]
+    fn serializer(&self) -> &S {
+        self.0.serializer()
+    }
+
+    fn try_read(&mut self) -> Result<Option<S::Message>, S::Error> {
+        let Some(message) = self.0.try_read()? else {
+            return Ok(None);
+        };
+
+        ChannelStream::new(self.0.reader())?.skip_to_end()?;
+
+        Ok(Some(message))
+    }
+
+    fn read(&mut self) -> Result<S::Message, S::Error> {
+        let message = self.0.read()?;
+
+        ChannelStream::new(self.0.reader())?.skip_to_end()?;
+
+        Ok(message)
+    }
+
+    fn write(&mut self, message: S::Message) -> Result<(), S::Error> {
+        self.0.write(message)?;
+
+        write_terminator(self.0.writer())?;
+
+        Ok(())
+    }
+}
+
+impl<C> StreamingChannel<C> {
+    /// Read the next message, together with a handle onto its streamed body
+    /// attachment (present even if the peer attached no body — it will
+    /// simply yield zero bytes). The channel cannot be read again until the
+    /// returned `ChannelStream` is dropped.
+    pub fn read_with_stream<R, W, S>(
+        &mut self
+    ) -> Result<(S::Message, ChannelStream<'_, R>), S::Error>
+    where
+        R: Read,
+        W: Write,
+        S: Serializer<R, W>,
+        S::Error: From<io::Error>,
+        C: OwnedChannel<R, W, S>
+    {
+        let message = self.0.read()?;
+
+        let body = ChannelStream::new(self.0.reader())?;
+
+        Ok((message, body))
+    }
+
+    /// Write `message`, then stream `body` (if any) after it as a sequence
+    /// of length-prefixed chunks of at most `chunk_size` bytes, terminated
+    /// by a zero-length chunk.
+    pub fn write_with_stream<R, W, S>(
+        &mut self,
+        message: S::Message,
+        mut body: Option<impl Read>,
+        chunk_size: usize
+    ) -> Result<(), S::Error>
+    where
+        R: Read,
+        W: Write,
+        S: Serializer<R, W>,
+        S::Error: From<io::Error>,
+        C: OwnedChannel<R, W, S>
+    {
+        self.0.write(message)?;
+
+        let writer = self.0.writer();
+
+        if let Some(body) = &mut body {
+            let mut buf = vec![0u8; chunk_size];
+
+            loop {
+                let n = body.read(&mut buf)?;
+
+                if n == 0 {
+                    break;
+                }
+
+                writer.write_all(&(n as u32).to_be_bytes())?;
+                writer.write_all(&buf[..n])?;
+            }
+        }
+
+        write_terminator(writer)?;
+
+        Ok(())
+    }
+}
+
+fn write_terminator<W: Write>(writer: &mut W) -> io::Result<()> {
+    writer.write_all(&0u32.to_be_bytes())?;
+    writer.flush()?;
+
+    Ok(())
+}